@@ -0,0 +1,287 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::parser::{BinOp, Expr, Stmt, UnaryOp};
+use crate::Error;
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum Value {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+}
+impl Value {
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+            Value::Bool(b) => *b,
+            _ => true,
+        }
+    }
+    pub fn is_equal(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(a), Value::Number(b)) => a == b,
+            (Value::Str(a), Value::Str(b)) => a == b,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Nil, Value::Nil) => true,
+            _ => false,
+        }
+    }
+    pub fn to_string(&self) -> String {
+        match self {
+            Value::Number(n) => n.to_string(),
+            Value::Str(s) => s.clone(),
+            Value::Bool(b) => b.to_string(),
+            Value::Nil => "nil".to_owned(),
+        }
+    }
+}
+
+pub type EnvRef = Rc<RefCell<Environment>>;
+
+#[derive(Debug)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    parent: Option<EnvRef>,
+}
+impl Environment {
+    pub fn new() -> EnvRef {
+        Rc::new(RefCell::new(Environment { values: HashMap::new(), parent: None }))
+    }
+    pub fn new_enclosed(parent: EnvRef) -> EnvRef {
+        Rc::new(RefCell::new(Environment { values: HashMap::new(), parent: Some(parent) }))
+    }
+    pub fn define(&mut self, name: String, value: Value) {
+        self.values.insert(name, value);
+    }
+    pub fn get(&self, name: &str, line: usize) -> Result<Value, Error> {
+        if let Some(value) = self.values.get(name) {
+            Ok(value.clone())
+        } else if let Some(parent) = &self.parent {
+            parent.borrow().get(name, line)
+        } else {
+            Err(Error::new(format!("Undefined variable '{}'.", name), String::new(), line))
+        }
+    }
+    pub fn assign(&mut self, name: &str, value: Value, line: usize) -> Result<(), Error> {
+        if self.values.contains_key(name) {
+            self.values.insert(name.to_owned(), value);
+            Ok(())
+        } else if let Some(parent) = &self.parent {
+            parent.borrow_mut().assign(name, value, line)
+        } else {
+            Err(Error::new(format!("Undefined variable '{}'.", name), String::new(), line))
+        }
+    }
+}
+
+pub fn eval_stmt(stmt: &Stmt, env: EnvRef) -> Result<(), Error> {
+    match stmt {
+        Stmt::Expr(expr) => {
+            eval_expr(expr, env)?;
+            Ok(())
+        }
+        Stmt::Print(expr) => {
+            let value = eval_expr(expr, env)?;
+            println!("{}", value.to_string());
+            Ok(())
+        }
+        Stmt::Let { lhs, rhs } => {
+            let value = match rhs {
+                Some(expr) => eval_expr(expr, env.clone())?,
+                None => Value::Nil,
+            };
+            env.borrow_mut().define(lhs.lexeme.clone(), value);
+            Ok(())
+        }
+        Stmt::Block(statements) => {
+            let child = Environment::new_enclosed(env);
+            for s in statements {
+                eval_stmt(s, child.clone())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn eval_expr(expr: &Expr, env: EnvRef) -> Result<Value, Error> {
+    match expr {
+        Expr::Literal(token) => literal_value(token),
+        Expr::Grouping(inner) => eval_expr(inner, env),
+        Expr::Variable(token) => env.borrow().get(&token.lexeme, token.line),
+        Expr::Unary { op, line, expr } => {
+            let value = eval_expr(expr, env)?;
+            eval_unary(*op, value, *line)
+        }
+        Expr::Binary { left, op: BinOp::Or, right, .. } => {
+            let l = eval_expr(left, env.clone())?;
+            if l.is_truthy() { Ok(l) } else { eval_expr(right, env) }
+        }
+        Expr::Binary { left, op: BinOp::And, right, .. } => {
+            let l = eval_expr(left, env.clone())?;
+            if !l.is_truthy() { Ok(l) } else { eval_expr(right, env) }
+        }
+        Expr::Binary { left, op, line, right } => {
+            let l = eval_expr(left, env.clone())?;
+            let r = eval_expr(right, env)?;
+            eval_binary(*op, l, r, *line)
+        }
+    }
+}
+
+fn literal_value(token: &crate::scanner::Token) -> Result<Value, Error> {
+    use crate::scanner::Literal;
+    match &token.literal {
+        Literal::Number(n) => Ok(Value::Number(*n)),
+        Literal::Str(s) => Ok(Value::Str(s.clone())),
+        Literal::Bool(b) => Ok(Value::Bool(*b)),
+        Literal::Nil => Ok(Value::Nil),
+        Literal::None => Err(Error::new("Unrecognized literal.".to_owned(), String::new(), token.line)),
+    }
+}
+
+fn eval_unary(op: UnaryOp, value: Value, line: usize) -> Result<Value, Error> {
+    match op {
+        UnaryOp::Minus => match value {
+            Value::Number(n) => Ok(Value::Number(-n)),
+            _ => Err(Error::new("Operand must be a number.".to_owned(), String::new(), line)),
+        },
+        UnaryOp::Bang => Ok(Value::Bool(!value.is_truthy())),
+    }
+}
+
+fn eval_binary(op: BinOp, left: Value, right: Value, line: usize) -> Result<Value, Error> {
+    match op {
+        // Short-circuited in eval_expr, which skips the right operand when the
+        // left one already decides the result.
+        BinOp::Or | BinOp::And => unreachable!("and/or are handled by eval_expr"),
+        BinOp::EqualEqual => Ok(Value::Bool(left.is_equal(&right))),
+        BinOp::BangEqual => Ok(Value::Bool(!left.is_equal(&right))),
+        BinOp::Plus => match (left, right) {
+            (Value::Number(a), Value::Number(b)) => Ok(Value::Number(a + b)),
+            (Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+            _ => Err(Error::new(
+                "Operands must be two numbers or two strings.".to_owned(),
+                String::new(),
+                line,
+            )),
+        },
+        BinOp::Minus => numeric_binary(left, right, line, |a, b| Value::Number(a - b)),
+        BinOp::Star => numeric_binary(left, right, line, |a, b| Value::Number(a * b)),
+        BinOp::Slash => numeric_binary(left, right, line, |a, b| Value::Number(a / b)),
+        BinOp::Greater => numeric_binary(left, right, line, |a, b| Value::Bool(a > b)),
+        BinOp::GreaterEqual => numeric_binary(left, right, line, |a, b| Value::Bool(a >= b)),
+        BinOp::Less => numeric_binary(left, right, line, |a, b| Value::Bool(a < b)),
+        BinOp::LessEqual => numeric_binary(left, right, line, |a, b| Value::Bool(a <= b)),
+    }
+}
+
+fn numeric_binary(left: Value, right: Value, line: usize, f: impl Fn(f64, f64) -> Value) -> Result<Value, Error> {
+    match (left, right) {
+        (Value::Number(a), Value::Number(b)) => Ok(f(a, b)),
+        _ => Err(Error::new("Operands must be numbers.".to_owned(), String::new(), line)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+
+    fn run_in_env(source: &str) -> EnvRef {
+        let mut scanner = Scanner::new(source.to_owned());
+        let tokens = scanner.scan_tokens().unwrap().clone();
+        let statements = Parser::new(tokens).parse().unwrap();
+        let env = Environment::new();
+        for s in &statements {
+            eval_stmt(s, env.clone()).unwrap();
+        }
+        env
+    }
+
+    fn eval(expr_source: &str) -> Value {
+        let env = run_in_env(&format!("var __result = {};", expr_source));
+        let value = env.borrow().get("__result", 0).unwrap();
+        value
+    }
+
+    #[test]
+    fn test_value_truthiness() {
+        assert!(!Value::Nil.is_truthy());
+        assert!(!Value::Bool(false).is_truthy());
+        assert!(Value::Bool(true).is_truthy());
+        assert!(Value::Number(0.0).is_truthy());
+        assert!(Value::Str(String::new()).is_truthy());
+    }
+
+    #[test]
+    fn test_arithmetic_respects_precedence() {
+        assert!(matches!(eval("1 + 2 * 3"), Value::Number(n) if n == 7.0));
+    }
+
+    #[test]
+    fn test_string_concatenation() {
+        assert!(matches!(eval("\"a\" + \"b\""), Value::Str(s) if s == "ab"));
+    }
+
+    #[test]
+    fn test_or_short_circuits_before_evaluating_right_operand() {
+        // `nonexistent` is never defined; if `or` evaluated it eagerly this
+        // would error instead of short-circuiting to `true`.
+        assert!(matches!(eval("true or nonexistent"), Value::Bool(true)));
+    }
+
+    #[test]
+    fn test_and_short_circuits_before_evaluating_right_operand() {
+        assert!(matches!(eval("false and nonexistent"), Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_and_or_still_evaluate_right_operand_when_needed() {
+        assert!(matches!(eval("true and false"), Value::Bool(false)));
+        assert!(matches!(eval("false or true"), Value::Bool(true)));
+    }
+
+    #[test]
+    fn test_environment_child_falls_back_to_parent() {
+        let parent = Environment::new();
+        parent.borrow_mut().define("x".to_owned(), Value::Number(1.0));
+        let child = Environment::new_enclosed(parent.clone());
+        assert!(matches!(child.borrow().get("x", 0).unwrap(), Value::Number(n) if n == 1.0));
+    }
+
+    #[test]
+    fn test_environment_define_shadows_parent() {
+        let parent = Environment::new();
+        parent.borrow_mut().define("x".to_owned(), Value::Number(1.0));
+        let child = Environment::new_enclosed(parent.clone());
+        child.borrow_mut().define("x".to_owned(), Value::Number(2.0));
+        assert!(matches!(child.borrow().get("x", 0).unwrap(), Value::Number(n) if n == 2.0));
+        assert!(matches!(parent.borrow().get("x", 0).unwrap(), Value::Number(n) if n == 1.0));
+    }
+
+    #[test]
+    fn test_environment_assign_updates_enclosing_scope() {
+        let parent = Environment::new();
+        parent.borrow_mut().define("x".to_owned(), Value::Number(1.0));
+        let child = Environment::new_enclosed(parent.clone());
+        child.borrow_mut().assign("x", Value::Number(3.0), 0).unwrap();
+        assert!(matches!(parent.borrow().get("x", 0).unwrap(), Value::Number(n) if n == 3.0));
+    }
+
+    #[test]
+    fn test_environment_undefined_variable_errors() {
+        let env = Environment::new();
+        assert!(env.borrow().get("missing", 7).is_err());
+    }
+
+    #[test]
+    fn test_block_does_not_leak_shadowed_variable_to_outer_scope() {
+        let env = run_in_env("var x = 1; { var x = 2; }");
+        assert!(matches!(env.borrow().get("x", 0).unwrap(), Value::Number(n) if n == 1.0));
+    }
+}
@@ -1,28 +1,67 @@
 use std::collections::HashMap;
 pub mod scanner;
+pub mod parser;
+pub mod interpreter;
 use scanner::*;
+use parser::Parser;
+use interpreter::Environment;
 
 pub fn run(source: &str) -> Result<(), Error> {
     let mut scanner = Scanner::new(String::from(source));
-    let tokens = scanner.scan_tokens()?;
-    for t in tokens {
-        println!("{}", t.to_string());
+    let tokens = scanner.scan_tokens()?.clone();
+    let mut parser = Parser::new(tokens);
+    let statements = parser.parse()?;
+    let env = Environment::new();
+    for s in &statements {
+        interpreter::eval_stmt(s, env.clone())?;
     }
     Ok(())
 }
 
+/// A single point in the source: `line` is 1-based for display, `column` is
+/// 0-based (it's used as a caret pad count in `Error::report`, not displayed
+/// directly), plus the raw char offset so callers can slice the original
+/// source back out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Site {
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+/// A range of source starting at `start` and covering `length` chars,
+/// attached to tokens so diagnostics can point at exactly what went wrong.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+    pub start: Site,
+    pub length: usize,
+}
+
 #[derive(Debug)]
 pub struct Error {
     pub message: String,
     pub r#where: String,
     pub line: usize,
+    pub span: Option<Span>,
+    pub source_line: Option<String>,
 }
 impl Error {
     pub fn new(message: String, r#where: String, line: usize) -> Error {
         Error {
             message,
             r#where,
-            line
+            line,
+            span: None,
+            source_line: None,
+        }
+    }
+    pub fn with_span(message: String, r#where: String, span: Span, source_line: String) -> Error {
+        Error {
+            message,
+            r#where,
+            line: span.start.line,
+            span: Some(span),
+            source_line: Some(source_line),
         }
     }
     pub fn to_string(&self) -> String {
@@ -30,6 +69,11 @@ impl Error {
     }
     pub fn report(&self) {
         eprintln!("{}", self.to_string());
+        if let (Some(span), Some(source_line)) = (&self.span, &self.source_line) {
+            eprintln!("{}", source_line);
+            let pointer: String = " ".repeat(span.start.column) + "^" + &"~".repeat(span.length.saturating_sub(1));
+            eprintln!("{}", pointer);
+        }
     }
 }
 impl std::fmt::Display for Error {
@@ -28,7 +28,7 @@ fn run_prompt() {
                 std::io::stdout().flush().unwrap();
                 match rlox::run(&input) {
                     Ok(_) => {},
-                    Err(err) => eprintln!("{}", err)
+                    Err(err) => err.report()
                 };
             }
             Err(err) => eprintln!("Error reading input: {}", err),
@@ -60,7 +60,7 @@ fn run_file(filename: &str) {
             std::process::exit(0);
         },
         Err(err) => { // Exit with error.
-            eprintln!("{}", err);
+            err.report();
             std::process::exit(1);
         }
     };
@@ -0,0 +1,304 @@
+use crate::scanner::{Token, TokenType};
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum BinOp {
+    Or, And,
+    EqualEqual, BangEqual,
+    Greater, GreaterEqual, Less, LessEqual,
+    Plus, Minus,
+    Star, Slash,
+}
+impl BinOp {
+    fn from_token_type(r#type: TokenType) -> Option<BinOp> {
+        match r#type {
+            TokenType::Or => Some(BinOp::Or),
+            TokenType::And => Some(BinOp::And),
+            TokenType::EqualEqual => Some(BinOp::EqualEqual),
+            TokenType::BangEqual => Some(BinOp::BangEqual),
+            TokenType::Greater => Some(BinOp::Greater),
+            TokenType::GreaterEqual => Some(BinOp::GreaterEqual),
+            TokenType::Less => Some(BinOp::Less),
+            TokenType::LessEqual => Some(BinOp::LessEqual),
+            TokenType::Plus => Some(BinOp::Plus),
+            TokenType::Minus => Some(BinOp::Minus),
+            TokenType::Star => Some(BinOp::Star),
+            TokenType::Slash => Some(BinOp::Slash),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[allow(dead_code)]
+pub enum UnaryOp {
+    Bang,
+    Minus,
+}
+impl UnaryOp {
+    fn from_token_type(r#type: TokenType) -> Option<UnaryOp> {
+        match r#type {
+            TokenType::Bang => Some(UnaryOp::Bang),
+            TokenType::Minus => Some(UnaryOp::Minus),
+            _ => None,
+        }
+    }
+}
+
+// Binding power of each binary operator. Unary operators bind tighter than
+// any of these, see `UNARY_PRECEDENCE` below.
+fn get_precedence(r#type: TokenType) -> Option<u8> {
+    match r#type {
+        TokenType::Or => Some(1),
+        TokenType::And => Some(2),
+        TokenType::EqualEqual | TokenType::BangEqual => Some(3),
+        TokenType::Greater | TokenType::GreaterEqual | TokenType::Less | TokenType::LessEqual => Some(4),
+        TokenType::Plus | TokenType::Minus => Some(5),
+        TokenType::Star | TokenType::Slash => Some(6),
+        _ => None,
+    }
+}
+const UNARY_PRECEDENCE: u8 = 7;
+fn min_precedence() -> u8 {
+    1
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum Expr {
+    Literal(Token),
+    Unary { op: UnaryOp, line: usize, expr: Box<Expr> },
+    Binary { left: Box<Expr>, op: BinOp, line: usize, right: Box<Expr> },
+    Grouping(Box<Expr>),
+    Variable(Token),
+}
+
+#[derive(Debug, Clone)]
+#[allow(dead_code)]
+pub enum Stmt {
+    Expr(Expr),
+    Print(Expr),
+    Let { lhs: Token, rhs: Option<Expr> },
+    Block(Vec<Stmt>),
+}
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    current: usize,
+}
+impl Parser {
+    pub fn new(tokens: Vec<Token>) -> Parser {
+        Parser { tokens, current: 0 }
+    }
+    pub fn parse(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut statements = Vec::new();
+        while !self.is_at_end() {
+            statements.push(self.parse_stmt()?);
+        }
+        Ok(statements)
+    }
+    fn parse_stmt(&mut self) -> Result<Stmt, Error> {
+        if self.check(TokenType::Print) {
+            self.advance();
+            let expr = self.parse_expr(min_precedence())?;
+            self.expect(TokenType::Semicolon, "Expect ';' after value.")?;
+            Ok(Stmt::Print(expr))
+        } else if self.check(TokenType::Var) {
+            self.advance();
+            let lhs = self.expect(TokenType::Identifier, "Expect variable name.")?;
+            let rhs = if self.check(TokenType::Equal) {
+                self.advance();
+                Some(self.parse_expr(min_precedence())?)
+            } else {
+                None
+            };
+            self.expect(TokenType::Semicolon, "Expect ';' after variable declaration.")?;
+            Ok(Stmt::Let { lhs, rhs })
+        } else if self.check(TokenType::LeftBrace) {
+            self.advance();
+            Ok(Stmt::Block(self.parse_block()?))
+        } else {
+            let expr = self.parse_expr(min_precedence())?;
+            self.expect(TokenType::Semicolon, "Expect ';' after expression.")?;
+            Ok(Stmt::Expr(expr))
+        }
+    }
+    fn parse_block(&mut self) -> Result<Vec<Stmt>, Error> {
+        let mut statements = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            statements.push(self.parse_stmt()?);
+        }
+        self.expect(TokenType::RightBrace, "Expect '}' after block.")?;
+        Ok(statements)
+    }
+    fn parse_expr(&mut self, min_prec: u8) -> Result<Expr, Error> {
+        let mut left = self.parse_prefix()?;
+        while let Some(prec) = get_precedence(self.peek().r#type) {
+            if prec < min_prec {
+                break;
+            }
+            let op_token = self.advance();
+            let op = BinOp::from_token_type(op_token.r#type).unwrap();
+            let right = self.parse_expr(prec + 1)?;
+            left = Expr::Binary { left: Box::new(left), op, line: op_token.line, right: Box::new(right) };
+        }
+        Ok(left)
+    }
+    fn parse_prefix(&mut self) -> Result<Expr, Error> {
+        if self.check(TokenType::Bang) || self.check(TokenType::Minus) {
+            let op_token = self.advance();
+            let op = UnaryOp::from_token_type(op_token.r#type).unwrap();
+            let expr = self.parse_expr(UNARY_PRECEDENCE)?;
+            return Ok(Expr::Unary { op, line: op_token.line, expr: Box::new(expr) });
+        }
+        self.parse_primary()
+    }
+    fn parse_primary(&mut self) -> Result<Expr, Error> {
+        if self.check(TokenType::False)
+            || self.check(TokenType::True)
+            || self.check(TokenType::Nil)
+            || self.check(TokenType::Number)
+            || self.check(TokenType::String)
+        {
+            return Ok(Expr::Literal(self.advance()));
+        }
+        if self.check(TokenType::Identifier) {
+            return Ok(Expr::Variable(self.advance()));
+        }
+        if self.check(TokenType::LeftParen) {
+            self.advance();
+            let expr = self.parse_expr(min_precedence())?;
+            self.expect(TokenType::RightParen, "Expect ')' after expression.")?;
+            return Ok(Expr::Grouping(Box::new(expr)));
+        }
+        Err(self.error("Expect expression."))
+    }
+    fn check(&self, r#type: TokenType) -> bool {
+        self.peek().r#type == r#type
+    }
+    fn peek(&self) -> Token {
+        self.tokens[self.current].clone()
+    }
+    fn is_at_end(&self) -> bool {
+        self.peek().r#type == TokenType::Eof
+    }
+    fn advance(&mut self) -> Token {
+        let token = self.peek();
+        if !self.is_at_end() {
+            self.current += 1;
+        }
+        token
+    }
+    fn expect(&mut self, r#type: TokenType, message: &str) -> Result<Token, Error> {
+        if self.check(r#type) {
+            Ok(self.advance())
+        } else {
+            Err(self.error(message))
+        }
+    }
+    fn error(&self, message: &str) -> Error {
+        let token = self.peek();
+        let r#where = if token.r#type == TokenType::Eof {
+            "at end".to_owned()
+        } else {
+            format!("at '{}'", token.lexeme)
+        };
+        Error::new(message.to_owned(), r#where, token.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scanner::Scanner;
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let mut scanner = Scanner::new(source.to_owned());
+        let tokens = scanner.scan_tokens().unwrap().clone();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    fn parse_single_expr(source: &str) -> Expr {
+        match &parse(source)[..] {
+            [Stmt::Expr(expr)] => expr.clone(),
+            other => panic!("expected a single expression statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plus_binds_looser_than_star() {
+        // 1 + 2 * 3 should parse as 1 + (2 * 3), not (1 + 2) * 3.
+        match parse_single_expr("1 + 2 * 3;") {
+            Expr::Binary { op: BinOp::Plus, right, .. } => {
+                assert!(matches!(*right, Expr::Binary { op: BinOp::Star, .. }));
+            }
+            other => panic!("expected a Plus at the top, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_binary_operators_are_left_associative() {
+        // 1 - 2 - 3 should parse as (1 - 2) - 3, not 1 - (2 - 3).
+        match parse_single_expr("1 - 2 - 3;") {
+            Expr::Binary { op: BinOp::Minus, left, right, .. } => {
+                assert!(matches!(*left, Expr::Binary { op: BinOp::Minus, .. }));
+                assert!(matches!(*right, Expr::Literal(_)));
+            }
+            other => panic!("expected a Minus at the top, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unary_binds_tighter_than_binary() {
+        // -1 * 2 should parse as (-1) * 2, not -(1 * 2).
+        match parse_single_expr("-1 * 2;") {
+            Expr::Binary { op: BinOp::Star, left, .. } => {
+                assert!(matches!(*left, Expr::Unary { op: UnaryOp::Minus, .. }));
+            }
+            other => panic!("expected a Star at the top, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_grouping_overrides_precedence() {
+        // (1 + 2) * 3 should parse as Star(Grouping(Plus(1, 2)), 3).
+        match parse_single_expr("(1 + 2) * 3;") {
+            Expr::Binary { op: BinOp::Star, left, .. } => {
+                assert!(matches!(*left, Expr::Grouping(_)));
+            }
+            other => panic!("expected a Star at the top, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_var_statement_with_initializer() {
+        match &parse("var x = 5;")[..] {
+            [Stmt::Let { lhs, rhs: Some(_) }] => assert_eq!(lhs.lexeme, "x"),
+            other => panic!("expected a single Let statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_var_statement_without_initializer() {
+        match &parse("var x;")[..] {
+            [Stmt::Let { lhs, rhs: None }] => assert_eq!(lhs.lexeme, "x"),
+            other => panic!("expected a Let statement with no initializer, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_block_statement() {
+        match &parse("{ print 1; print 2; }")[..] {
+            [Stmt::Block(inner)] => assert_eq!(inner.len(), 2),
+            other => panic!("expected a single Block statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unexpected_token_is_an_error() {
+        let mut scanner = Scanner::new("1 +;".to_owned());
+        let tokens = scanner.scan_tokens().unwrap().clone();
+        assert!(Parser::new(tokens).parse().is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use crate::Error;
+use crate::{Error, Site, Span};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 #[allow(dead_code)]
@@ -25,21 +25,44 @@ pub enum TokenType {
     Eof
 }
 
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
-#[derive(Debug)]
+pub enum Literal {
+    Number(f64),
+    Str(String),
+    Bool(bool),
+    Nil,
+    None,
+}
+impl std::fmt::Display for Literal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Literal::Number(n) => write!(f, "{}", n),
+            Literal::Str(s) => write!(f, "{}", s),
+            Literal::Bool(b) => write!(f, "{}", b),
+            Literal::Nil => write!(f, "nil"),
+            Literal::None => write!(f, ""),
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub struct Token {
-    r#type: TokenType,
-    lexeme: String,
-    literal: String,
-    line: usize,
+    pub(crate) r#type: TokenType,
+    pub(crate) lexeme: String,
+    pub(crate) literal: Literal,
+    pub(crate) line: usize,
+    pub(crate) span: Span,
 }
 impl Token {
-    pub fn new(r#type: TokenType, lexeme: String, literal: String, line: usize) -> Token {
+    pub fn new(r#type: TokenType, lexeme: String, literal: Literal, line: usize, span: Span) -> Token {
         Token {
             r#type,
             lexeme,
             literal,
             line,
+            span,
         }
     }
     pub fn to_string(&self) -> String {
@@ -49,15 +72,19 @@ impl Token {
 
 #[allow(dead_code)]
 pub struct Scanner {
-    source: String,
+    source: Vec<char>,
     tokens: Vec<Token>,
     start: usize,
     current: usize,
     line: usize,
+    column: usize,
+    start_line: usize,
+    start_column: usize,
     keywords: HashMap<String, TokenType>
 }
 impl Scanner {
     pub fn new(source: String) -> Scanner {
+        let source: Vec<char> = source.chars().collect();
         let mut keywords = HashMap::new();
         keywords.insert("and".to_owned(), TokenType::And);
         keywords.insert("or".to_owned(), TokenType::Or);
@@ -81,6 +108,9 @@ impl Scanner {
             start: 0,
             current: 0,
             line: 1,
+            column: 0,
+            start_line: 1,
+            start_column: 0,
             keywords,
         }
     }
@@ -88,9 +118,12 @@ impl Scanner {
         while !self.is_at_end() {
           // We are at the beginning of the next lexeme.
           self.start = self.current;
+          self.start_line = self.line;
+          self.start_column = self.column;
           self.scan_token()?;
         }
-        self.tokens.push(Token::new(TokenType::Eof, "".to_owned(), "".to_owned(), self.line));
+        let eof_site = Site { line: self.line, column: self.column, offset: self.current };
+        self.tokens.push(Token::new(TokenType::Eof, "".to_owned(), Literal::None, self.line, Span { start: eof_site, length: 0 }));
         Ok(&self.tokens)
     }
     fn scan_token(&mut self) -> Result<(), Error>{
@@ -171,7 +204,7 @@ impl Scanner {
                           if nesting_layer > 0 {
                               s.push_str(": missing closing */");
                           }
-                          return Err(Error::new(s, self.line));
+                          return Err(self.error_here(s, 2));
                       } else if self.peek() == '/' && self.peek_next() == '*' {
                           self.advance(); // Consume nested /
                           self.advance(); // Consume nested *
@@ -196,16 +229,14 @@ impl Scanner {
           '"' => {
               self.read_string()?;
           },
-          '\n' => {
-              self.line += 1;
-          },
+          '\n' => {}, // Line/column bookkeeping already happened in advance().
           _ => {
               if is_digit(c) {
                   self.read_number()?;
               } else if is_alpha(c) {
                   self.read_identifier()?;
               } else {
-                  return Err(Error::new("Unexpected character.".to_owned(), String::new(), self.line));
+                  return Err(self.error_here("Unexpected character.".to_owned(), 1));
               }
           }
       };
@@ -224,7 +255,9 @@ impl Scanner {
               self.advance();
           }
         }
-        self.add_token_literal(TokenType::Number, substring(&self.source, self.start, self.current));
+        let text = substring(&self.source, self.start, self.current);
+        let value: f64 = text.parse().expect("scanned number lexeme must be valid");
+        self.add_token_literal(TokenType::Number, Literal::Number(value));
         Ok(())
     }
     fn read_identifier(&mut self) -> Result<(), Error>{
@@ -236,43 +269,119 @@ impl Scanner {
             Some(t) => *t,
             None => TokenType::Identifier
         };
-        if t == TokenType::Identifier {
-            self.add_token_literal(t, text);
-        } else {
-            self.add_token(t);
+        match t {
+            TokenType::True => self.add_token_literal(t, Literal::Bool(true)),
+            TokenType::False => self.add_token_literal(t, Literal::Bool(false)),
+            TokenType::Nil => self.add_token_literal(t, Literal::Nil),
+            _ => self.add_token(t),
         }
         Ok(())
     }
     fn read_string(&mut self) -> Result<(), Error> {
+        let mut value = String::new();
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+            let escape_start = Site { line: self.line, column: self.column, offset: self.current };
+            let c = self.advance();
+            if c == '\\' {
+                value.push(self.read_escape(escape_start)?);
+            } else {
+                // Literal newlines are allowed inside strings; advance() already
+                // bumped self.line for us.
+                value.push(c);
             }
-            self.advance();
         }
 
         // Unterminated string.
         if self.is_at_end() {
-            Err(Error::new("Unterminated string".to_owned(), String::new(), self.line))
+            Err(self.error_here("Unterminated string".to_owned(), 1))
         } else {
             // The closing ".
             self.advance();
-            self.add_token_literal(TokenType::String, substring(&self.source, self.start + 1, self.current - 1));
+            self.add_token_literal(TokenType::String, Literal::Str(value));
             Ok(())
         }
     }
+    // `escape_start` anchors diagnostics to the backslash that opened this escape,
+    // captured by the caller before it was consumed (the same discipline `error_here`
+    // uses for `start_line`/`start_column`), so the caret lands under the escape
+    // itself rather than wherever scanning happened to be once the error was noticed.
+    fn read_escape(&mut self, escape_start: Site) -> Result<char, Error> {
+        if self.is_at_end() {
+            return Err(self.error_at(escape_start, "Incomplete escape sequence".to_owned(), 1));
+        }
+        let c = self.advance();
+        match c {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '0' => Ok('\0'),
+            'u' => self.read_unicode_escape(escape_start),
+            _ => Err(self.error_at(escape_start, format!("Invalid escape sequence '\\{}'", c), 2)),
+        }
+    }
+    fn read_unicode_escape(&mut self, escape_start: Site) -> Result<char, Error> {
+        if self.peek() != '{' {
+            return Err(self.error_at(escape_start, "Expected '{' after \\u".to_owned(), 2));
+        }
+        self.advance(); // Consume the '{'.
+        let mut digits = String::new();
+        while self.peek() != '}' && !self.is_at_end() {
+            digits.push(self.advance());
+        }
+        if self.is_at_end() {
+            return Err(self.error_at(escape_start, "Unterminated unicode escape".to_owned(), digits.len() + 3));
+        }
+        self.advance(); // Consume the '}'.
+        let code = u32::from_str_radix(&digits, 16).map_err(|_| {
+            self.error_at(escape_start, format!("Invalid unicode escape '\\u{{{}}}'", digits), digits.len() + 4)
+        })?;
+        char::from_u32(code).ok_or_else(|| {
+            self.error_at(escape_start, format!("Invalid unicode scalar value '\\u{{{}}}'", digits), digits.len() + 4)
+        })
+    }
     fn add_token(&mut self, r#type: TokenType) {
-        self.add_token_literal(r#type, "".to_owned());
+        self.add_token_literal(r#type, Literal::None);
     }
-    fn add_token_literal(&mut self, r#type: TokenType, literal: String) {
-        self.tokens.push(Token::new(r#type, substring(&self.source, self.start, self.current), literal, self.line));
+    fn add_token_literal(&mut self, r#type: TokenType, literal: Literal) {
+        let span = Span {
+            start: Site { line: self.start_line, column: self.start_column, offset: self.start },
+            length: self.current - self.start,
+        };
+        self.tokens.push(Token::new(r#type, substring(&self.source, self.start, self.current), literal, self.start_line, span));
+    }
+    fn error_here(&self, message: String, length: usize) -> Error {
+        let span = Span {
+            start: Site { line: self.start_line, column: self.start_column, offset: self.start },
+            length,
+        };
+        Error::with_span(message, String::new(), span, self.line_text(self.start_line))
+    }
+    fn error_at(&self, start: Site, message: String, length: usize) -> Error {
+        let span = Span { start, length };
+        Error::with_span(message, String::new(), span, self.line_text(start.line))
+    }
+    fn line_text(&self, line: usize) -> String {
+        self.source
+            .split(|&c| c == '\n')
+            .nth(line.saturating_sub(1))
+            .map(|chars| chars.iter().collect())
+            .unwrap_or_default()
     }
     fn is_at_end(&self) -> bool {
         self.current >= str_len(&self.source)
     }
     fn advance(&mut self) -> char {
+        let c = char_at(&self.source, self.current);
         self.current += 1;
-        char_at(&self.source, self.current - 1)
+        if c == '\n' {
+            self.line += 1;
+            self.column = 0;
+        } else {
+            self.column += 1;
+        }
+        c
     }
     fn advance_if_equal(&mut self, c: char) -> bool {
         let p = self.peek();
@@ -298,26 +407,22 @@ impl Scanner {
         }
     }
 }
-// Helper functions
-fn str_len(s: &String) -> usize {
-    s.chars().count()
+// Helper functions. These index into a pre-collected `Vec<char>` rather than
+// re-walking a `chars()` iterator, so scanning stays O(n) in the source length.
+fn str_len(s: &[char]) -> usize {
+    s.len()
 }
-fn char_at(s: &String, pos: usize) -> char {
-    if pos >= str_len(&s) {
-        '\0'
-    } else {
-        s.chars().skip(pos).next().unwrap()
-    }
+fn char_at(s: &[char], pos: usize) -> char {
+    *s.get(pos).unwrap_or(&'\0')
 }
-fn substring(s: &String, start: usize, mut end: usize) -> String {
-    if start > end || start >= str_len(&s) {
+fn substring(s: &[char], start: usize, mut end: usize) -> String {
+    if start > end || start >= s.len() {
         "".to_owned()
     } else {
-        if end > str_len(&s) {
-            end = str_len(&s);
+        if end > s.len() {
+            end = s.len();
         }
-        let text: String = s.chars().skip(start).take(end - start).collect();
-        text
+        s[start..end].iter().collect()
     }
 }
 fn is_digit(c: char) -> bool {
@@ -338,14 +443,14 @@ mod tests {
     // Helper function tests.
     #[test]
     fn test_str_len() {
-        let s1 = String::from("Hello world!");
+        let s1: Vec<char> = "Hello world!".chars().collect();
         assert_eq!(str_len(&s1), 12);
-        let s2 = String::from("this is a test");
+        let s2: Vec<char> = "this is a test".chars().collect();
         assert_eq!(str_len(&s2), 14);
     }
     #[test]
     fn test_char_at() {
-        let s = String::from("this is a test");
+        let s: Vec<char> = "this is a test".chars().collect();
         assert_eq!(char_at(&s, 1), 'h'); // valid
         assert_eq!(char_at(&s, 5), 'i'); // valid
         assert_eq!(char_at(&s, 8), 'a'); // valid
@@ -353,7 +458,7 @@ mod tests {
     }
     #[test]
     fn test_substring() {
-        let s = String::from("this is a test");
+        let s: Vec<char> = "this is a test".chars().collect();
         assert_eq!(substring(&s, 0, 4), "this".to_owned()); // valid
         assert_eq!(substring(&s, 6, 12), "s a te".to_owned()); // valid
         assert_eq!(substring(&s, 8, 20), "a test".to_owned()); // end > s.len()
@@ -388,26 +493,32 @@ mod tests {
     }
 
     // Token tests
+    fn test_span() -> Span {
+        Span { start: Site { line: 23, column: 4, offset: 10 }, length: 4 }
+    }
     #[test]
     fn test_token_constructor() {
         let t = Token::new(
             TokenType::Equal,
             "asdf".to_owned(),
-            "==".to_owned(),
-            23
+            Literal::Str("==".to_owned()),
+            23,
+            test_span(),
         );
         assert_eq!(t.r#type, TokenType::Equal);
         assert_eq!(t.lexeme, "asdf".to_owned());
-        assert_eq!(t.literal, "==".to_owned());
+        assert_eq!(t.literal, Literal::Str("==".to_owned()));
         assert_eq!(t.line, 23);
+        assert_eq!(t.span, test_span());
     }
     #[test]
     fn test_token_to_string() {
         let t = Token::new(
             TokenType::Equal,
             "asdf".to_owned(),
-            "==".to_owned(),
-            23
+            Literal::Str("==".to_owned()),
+            23,
+            test_span(),
         );
         assert_eq!(t.to_string(), String::from("Equal =="));
     }
@@ -416,27 +527,72 @@ mod tests {
     #[test]
     fn test_scanner_constructor() {
         let s = Scanner::new("var x = 5;".to_owned());
-        assert_eq!(s.source, "var x = 5;".to_owned());
+        assert_eq!(s.source, "var x = 5;".chars().collect::<Vec<char>>());
         assert_eq!(s.tokens.len(), 0);
         assert_eq!(s.start, 0);
         assert_eq!(s.current, 0);
         assert_eq!(s.line, 1);
         assert_eq!(*s.keywords.get("nil").unwrap(), TokenType::Nil);
     }
+    #[test]
     fn test_scanner_scanning() {
         let mut s = Scanner::new("var x = 5;".to_owned());
-        let mut t = s.scan_tokens();
+        let t = s.scan_tokens();
         match t {
             Ok(tokens) => {
                 assert_eq!(tokens[0].to_string(), "Var ".to_owned());
-                assert_eq!(tokens[1].to_string(), "Identifier x".to_owned());
+                assert_eq!(tokens[1].to_string(), "Identifier ".to_owned());
                 assert_eq!(tokens[2].to_string(), "Equal ".to_owned());
                 assert_eq!(tokens[3].to_string(), "Number 5".to_owned());
                 assert_eq!(tokens[4].to_string(), "Semicolon ".to_owned());
             },
-            Err(err) => {
+            Err(_err) => {
                 assert!(false);
             }
         }
     }
+
+    // Escape decoding tests
+    fn scan_string_literal(source: &str) -> Result<String, Error> {
+        let mut s = Scanner::new(format!("\"{}\"", source));
+        let tokens = s.scan_tokens()?;
+        match &tokens[0].literal {
+            Literal::Str(value) => Ok(value.clone()),
+            other => panic!("expected a Str literal, got {:?}", other),
+        }
+    }
+    #[test]
+    fn test_escape_newline_and_tab() {
+        assert_eq!(scan_string_literal("a\\nb\\tc").unwrap(), "a\nb\tc".to_owned());
+    }
+    #[test]
+    fn test_escape_quote() {
+        assert_eq!(scan_string_literal("say \\\"hi\\\"").unwrap(), "say \"hi\"".to_owned());
+    }
+    #[test]
+    fn test_escape_unicode() {
+        assert_eq!(scan_string_literal("\\u{1F600}").unwrap(), "\u{1F600}".to_owned());
+    }
+    #[test]
+    fn test_escape_invalid_sequence_is_an_error() {
+        assert!(scan_string_literal("bad \\q escape").is_err());
+    }
+    #[test]
+    fn test_escape_invalid_sequence_points_at_the_backslash() {
+        let mut s = Scanner::new("\"bad \\q escape\"".to_owned());
+        let err = s.scan_tokens().unwrap_err();
+        let span = err.span.expect("escape error should carry a span");
+        // The opening quote is column 0, so "bad " puts the backslash at column 5.
+        assert_eq!(span.start.column, 5);
+    }
+    #[test]
+    fn test_escape_incomplete_at_eof_is_an_error() {
+        // A lone backslash at the very end of the source never completes.
+        let mut s = Scanner::new("\"bad \\".to_owned());
+        assert!(s.scan_tokens().is_err());
+    }
+    #[test]
+    fn test_escape_unterminated_unicode_is_an_error() {
+        assert!(scan_string_literal("\\u{1F600").is_err());
+    }
 }